@@ -0,0 +1,29 @@
+// Error definitions for the counter program
+use solana_program::program_error::ProgramError; // Import the error type the runtime expects handlers to return
+use thiserror::Error; // Import the derive macro used to implement std::error::Error
+
+// Errors specific to the counter program's arithmetic and validation
+#[derive(Debug, Error)]
+pub enum CounterError {
+    #[error("counter overflowed while incrementing")]
+    Overflow,
+
+    #[error("counter underflowed while decrementing")]
+    Underflow,
+
+    #[error("transaction also invokes a forbidden program")]
+    ForbiddenProgramPresent,
+
+    #[error("account is a legacy, unclaimed account; submit ClaimAuthority first")]
+    AccountNotClaimed,
+
+    #[error("account has already claimed an authority")]
+    AlreadyClaimed,
+}
+
+// Convert a CounterError into the ProgramError the runtime expects from process_instruction
+impl From<CounterError> for ProgramError {
+    fn from(e: CounterError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,53 @@
+// Instruction definitions for the counter program
+use borsh::{BorshDeserialize, BorshSerialize}; // Import traits for serialization and deserialization
+use solana_program::{program_error::ProgramError, pubkey::Pubkey}; // Import the ProgramError type used to signal decoding failures and Pubkey for account references
+
+// Arguments accompanying instructions that carry a counter value
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+pub struct CounterArgs {
+    pub value: u32, // The value to apply to the counter
+}
+
+// Arguments for UpdateGuarded: the new value plus a program id that must not also appear
+// as an instruction in the same transaction
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+pub struct UpdateGuardedArgs {
+    pub value: u32,              // The value to apply to the counter
+    pub forbidden_program: Pubkey, // A program id that may not be invoked alongside this instruction
+}
+
+// All instructions supported by the counter program
+#[derive(Debug)]
+pub enum CounterInstructions {
+    Increment(CounterArgs), // Increase the counter by `value` (defaults to 1 when no payload is given)
+    Decrement(CounterArgs), // Decrease the counter by `value` (defaults to 1 when no payload is given)
+    Update(CounterArgs),    // Set the counter to `value`
+    Reset,                  // Set the counter back to 0
+    Initialize,             // Create and fund the counter account via a System Program CPI
+    UpdateGuarded(UpdateGuardedArgs), // Like Update, but rejects transactions that also invoke `forbidden_program`
+    ClaimAuthority, // Migrate a legacy, unversioned account and stamp the signer in as its authority
+}
+
+impl CounterInstructions {
+    // Decode the first byte of `input` as a tag and the remainder as the instruction's payload
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(match tag {
+            0 => Self::Increment(
+                CounterArgs::try_from_slice(rest).unwrap_or(CounterArgs { value: 1 }),
+            ),
+            1 => Self::Decrement(
+                CounterArgs::try_from_slice(rest).unwrap_or(CounterArgs { value: 1 }),
+            ),
+            2 => Self::Update(CounterArgs::try_from_slice(rest)?),
+            3 => Self::Reset,
+            4 => Self::Initialize,
+            5 => Self::UpdateGuarded(UpdateGuardedArgs::try_from_slice(rest)?),
+            6 => Self::ClaimAuthority,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+}
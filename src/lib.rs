@@ -1,30 +1,41 @@
 // Import necessary traits and modules
-use borsh::{BorshDeserialize, BorshSerialize}; // Import traits for serialization and deserialization
-use borsh_derive::{BorshDeserialize, BorshSerialize}; // Import macros for deriving serialization and deserialization
+use borsh::BorshSerialize; // Import the trait used to serialize state back into account data
 use solana_program::{ // Import modules from the Solana program library
     account_info::{next_account_info, AccountInfo}, // Import account info module for managing accounts
     entrypoint, // Import module for defining entry points to the program
     entrypoint::ProgramResult, // Import module for program result handling
     msg, // Import module for logging messages
+    program::invoke_signed, // Import the helper for CPIs signed with PDA seeds
+    program_error::ProgramError, // Import the error type returned when account validation fails
     pubkey::Pubkey, // Import module for managing public keys
+    rent::Rent, // Import the rent sysvar type for computing the rent-exempt minimum
+    system_instruction, // Import the System Program instruction builders
+    sysvar::instructions::{self as instructions_sysvar, load_instruction_at_checked}, // Import helpers for inspecting sibling instructions
+    sysvar::Sysvar, // Import the trait used to fetch sysvars such as Rent
 };
+use std::mem; // Import the mem module from the standard library for memory manipulation
 
+use crate::error::CounterError; // Import custom error module
 use crate::instructions::CounterInstructions; // Import custom instructions module
+use crate::state::{migrate, CounterAccount, CURRENT_VERSION}; // Import the versioned account state and its migration helper
 
+pub mod error; // Declare a submodule named "error"
 pub mod instructions; // Declare a submodule named "instructions"
+pub mod state; // Declare a submodule named "state"
 
-// Define a struct representing a counter account
-#[derive(Debug, BorshDeserialize, BorshSerialize)] // Derive traits for serialization and deserialization
-pub struct CounterAccount {
-    pub counter: u32, // Define a public field named "counter" of type u32
-}
+// The seed prefix used to derive a counter account's PDA from its payer
+const COUNTER_SEED: &[u8] = b"counter";
+
+// The number of bytes a current-layout counter account occupies: a version byte, the counter,
+// and its authority
+const COUNTER_ACCOUNT_SPACE: usize = 1 + mem::size_of::<u32>() + mem::size_of::<Pubkey>();
 
 // Declare an entry point function for the program, which will handle instruction processing
 entrypoint!(process_instruction);
 
 // Define the process_instruction function, which executes instructions received by the program
 pub fn process_instruction(
-    _program_id: &Pubkey,                     // The program's public key (unused)
+    program_id: &Pubkey,                      // The program's public key
     accounts: &[AccountInfo],                 // Array of accounts involved in the transaction
     instructions_data: &[u8],                 // Binary data containing instructions for the program
 ) -> ProgramResult {                         // Return type indicating success or failure of the program
@@ -37,22 +48,69 @@ pub fn process_instruction(
 
     // Create an iterator over the accounts array to access each account
     let accounts_iter = &mut accounts.iter();
-    
+
+    // Initialize has no existing account to deserialize, so it is handled up front
+    if let CounterInstructions::Initialize = instruction {
+        return initialize_counter(program_id, accounts_iter);
+    }
+
     // Retrieve the next account from the iterator
     let account = next_account_info(accounts_iter)?;
 
-    // Deserialize the account data into a CounterAccount struct
-    let mut counter_account = CounterAccount::try_from_slice(&account.data.borrow())?;
+    // Reject accounts this program doesn't own; their data can't be trusted as a CounterAccount
+    if account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // Every remaining instruction mutates state, so the authority must sign regardless of whether
+    // the account still needs migrating from a legacy layout
+    let authority = next_account_info(accounts_iter)?;
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Deserialize the account data, transparently migrating a legacy (unversioned) layout and
+    // stamping the signer in as its authority (the account's owner stays this program)
+    //
+    // A true V1 legacy account (bare u32 counter, created before `Initialize` and its PDA scheme
+    // existed) has no relationship to `find_program_address([COUNTER_SEED, signer], program_id)`,
+    // so there is no way to verify an "original creator" for it. `migrated` below is used to
+    // confine that claim to the dedicated `ClaimAuthority` instruction instead of letting it ride
+    // along as a side effect of whichever instruction happens to touch the account first.
+    let (mut counter_account, migrated) = migrate(&account.data.borrow(), *authority.key)?;
+
+    // A legacy account has no authority of its own to check a signature against; it must be
+    // claimed via `ClaimAuthority` before any other instruction will act on it. Conversely,
+    // `ClaimAuthority` itself only makes sense against an account that still needs claiming.
+    match (migrated, &instruction) {
+        (true, CounterInstructions::ClaimAuthority) => {}
+        (true, _) => return Err(CounterError::AccountNotClaimed.into()),
+        (false, CounterInstructions::ClaimAuthority) => {
+            return Err(CounterError::AlreadyClaimed.into())
+        }
+        (false, _) => {}
+    }
+
+    // A pre-existing (already-versioned) account must have been signed by its recorded authority
+    if counter_account.authority != *authority.key {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
 
     // Match the decoded instruction to perform corresponding actions
     match instruction {
-        // If the instruction is Increment, increase the counter by 1
+        // If the instruction is Increment, increase the counter by the given value
         CounterInstructions::Increment(args) => {
-            counter_account.counter += args.value;
+            counter_account.counter = counter_account
+                .counter
+                .checked_add(args.value)
+                .ok_or(CounterError::Overflow)?;
         }
-        // If the instruction is Decrement, decrease the counter by 1
+        // If the instruction is Decrement, decrease the counter by the given value
         CounterInstructions::Decrement(args) => {
-            counter_account.counter -= args.value;
+            counter_account.counter = counter_account
+                .counter
+                .checked_sub(args.value)
+                .ok_or(CounterError::Underflow)?;
         }
         // If the instruction is Reset, set the counter to 0
         CounterInstructions::Reset => {
@@ -62,15 +120,141 @@ pub fn process_instruction(
         CounterInstructions::Update(args) => {
             counter_account.counter = args.value;
         }
+        // If the instruction is UpdateGuarded, set the counter only if no sibling instruction
+        // in this transaction targets the caller-supplied forbidden program
+        CounterInstructions::UpdateGuarded(args) => {
+            let instructions_sysvar_account = next_account_info(accounts_iter)?;
+            if *instructions_sysvar_account.key != instructions_sysvar::id() {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            // There's no direct "instruction count" getter on this sysvar, so scan every
+            // instruction in the transaction - both before and after this one - until
+            // load_instruction_at_checked reports the index is out of bounds. A forbidden program
+            // bundled on either side of UpdateGuarded must be caught, not just ones that precede
+            // it. Only treat the out-of-bounds case as "no more instructions" - any other error
+            // (e.g. a malformed sysvar account) must surface, not be swallowed into an approval.
+            let mut index = 0;
+            loop {
+                match load_instruction_at_checked(index, instructions_sysvar_account) {
+                    Ok(sibling) => {
+                        if sibling.program_id == args.forbidden_program {
+                            return Err(CounterError::ForbiddenProgramPresent.into());
+                        }
+                        index += 1;
+                    }
+                    Err(ProgramError::InvalidArgument) => break,
+                    Err(err) => return Err(err),
+                }
+            }
+
+            counter_account.counter = args.value;
+        }
+        // `migrate` above already stamped the signer in as `counter_account.authority`; all
+        // that's left is to persist the now-claimed, version-tagged account below
+        CounterInstructions::ClaimAuthority => {}
+        // Initialize is handled above before an existing account is assumed to exist
+        CounterInstructions::Initialize => unreachable!(),
     }
 
-    // Serialize the updated counter_account back into the account data
-    counter_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
-    
+    // Grow the account to the current layout's size before writing, in case this is the first
+    // write after migrating from a smaller, legacy-sized account
+    // Growing the account doesn't move any lamports, so a legacy account funded only for its
+    // smaller, pre-migration size would otherwise leave the runtime's rent-exemption check to
+    // reject the transaction with no clue why. Surface that plainly instead: the caller must top
+    // up the account's lamports (e.g. via a System Program transfer) before its first mutating
+    // instruction after migration.
+    if account.data_len() < COUNTER_ACCOUNT_SPACE {
+        let required_lamports = Rent::get()?.minimum_balance(COUNTER_ACCOUNT_SPACE);
+        ensure_rent_exempt(account.lamports(), required_lamports)?;
+        account.realloc(COUNTER_ACCOUNT_SPACE, false)?;
+    }
+
+    // Serialize the updated counter_account back into the account data, behind the version byte
+    let mut account_data = account.data.borrow_mut();
+    account_data[0] = CURRENT_VERSION;
+    counter_account.serialize(&mut &mut account_data[1..])?;
+
     // Return Ok(()) to indicate successful execution of the instruction
     Ok(())
 }
 
+// Create and fund the counter account via a System Program CPI, then write its initial state.
+//
+// Accounts expected (in order): the fee-payer (signer, writable), the counter PDA derived from
+// `[COUNTER_SEED, payer.key]` (writable), and the System Program.
+//
+// NOTE: this test harness is plain `#[cfg(test)]` with no `solana-program-test`/banks client, so
+// the `invoke_signed` CPI below can't be exercised end-to-end here; only the PDA-mismatch
+// rejection and the deterministic `initial_counter_account_data` below have test coverage. A real
+// integration test exercising the CPI and reading back the created account belongs in a
+// `solana-program-test` harness once this crate has a manifest to add that dev-dependency to.
+fn initialize_counter(
+    program_id: &Pubkey,
+    accounts_iter: &mut std::slice::Iter<AccountInfo>,
+) -> ProgramResult {
+    // Retrieve the payer, the counter PDA to create, and the system program
+    let payer = next_account_info(accounts_iter)?;
+    let counter_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // Re-derive the PDA from the payer and confirm the caller passed the right account
+    let (pda, bump_seed) = Pubkey::find_program_address(&[COUNTER_SEED, payer.key.as_ref()], program_id);
+    if pda != *counter_account.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // The account needs to hold a version byte plus a serialized CounterAccount (counter + authority)
+    let space = COUNTER_ACCOUNT_SPACE;
+    let lamports = Rent::get()?.minimum_balance(space);
+
+    // Ask the System Program to create the account, signing with the PDA's derived seeds
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            counter_account.key,
+            lamports,
+            space as u64,
+            program_id,
+        ),
+        &[payer.clone(), counter_account.clone(), system_program.clone()],
+        &[&[COUNTER_SEED, payer.key.as_ref(), &[bump_seed]]],
+    )?;
+
+    // Write the initial, zeroed counter state into the freshly created account, with the payer
+    // as its authority and tagged with the current schema version
+    let mut account_data = counter_account.data.borrow_mut();
+    account_data.copy_from_slice(&initial_counter_account_data(payer.key));
+
+    msg!("Initialized counter account {}", counter_account.key);
+
+    Ok(())
+}
+
+// Build the initial on-disk bytes (version byte + serialized, zeroed CounterAccount) that
+// initialize_counter writes once the account has been created via CPI. Pulled out into a pure
+// function so this deterministic part of Initialize's behavior can be unit-tested independently
+// of the CPI itself.
+fn initial_counter_account_data(payer_key: &Pubkey) -> Vec<u8> {
+    let initial_state = CounterAccount {
+        counter: 0,
+        authority: *payer_key,
+    };
+    let mut data = vec![CURRENT_VERSION];
+    data.extend(initial_state.try_to_vec().unwrap());
+    data
+}
+
+// Reject growing an account whose lamport balance wouldn't cover `required_lamports`, the
+// rent-exempt minimum for its post-realloc size. Pulled out into a pure function, the same way
+// `initial_counter_account_data` is, so this decision can be unit-tested independently of the
+// Rent sysvar, which this crate's plain `#[cfg(test)]` harness has no way to provide.
+fn ensure_rent_exempt(lamports: u64, required_lamports: u64) -> ProgramResult {
+    if lamports < required_lamports {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    Ok(())
+}
 
 // This module contains tests for the counter program.
 #[cfg(test)]
@@ -79,8 +263,44 @@ mod test {
     use super::*;
     // Import specific items from the solana_program crate.
     use solana_program::{clock::Epoch, pubkey::Pubkey};
-    // Import the mem module from the standard library for memory manipulation.
-    use std::mem;
+    // Import the deserialization trait used to decode CounterAccount back out of raw bytes.
+    use borsh::BorshDeserialize;
+
+    // Build the instructions-sysvar account data for a transaction made up of `program_ids`, one
+    // no-op instruction (no accounts, no data) per program, with `current_index` marked as the
+    // instruction currently executing. Mirrors the real wire format `load_instruction_at_checked`
+    // parses: an instruction count, then a u16 byte-offset table (one entry per instruction) that
+    // `load_instruction_at` uses for random access, then the instruction bodies themselves, then
+    // the current instruction index as the final two bytes.
+    fn build_instructions_sysvar_data(program_ids: &[Pubkey], current_index: u16) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(program_ids.len() as u16).to_le_bytes());
+
+        // Reserve the offset table up front; each entry is patched in below once we know where
+        // its instruction body actually starts.
+        let offsets_start = data.len();
+        data.extend(std::iter::repeat_n(0u8, program_ids.len() * 2));
+
+        for (i, program_id) in program_ids.iter().enumerate() {
+            let instruction_offset = data.len() as u16;
+            data[offsets_start + i * 2..offsets_start + i * 2 + 2]
+                .copy_from_slice(&instruction_offset.to_le_bytes());
+
+            data.extend_from_slice(&0u16.to_le_bytes()); // num_accounts
+            data.extend_from_slice(program_id.as_ref());
+            data.extend_from_slice(&0u16.to_le_bytes()); // data_len
+        }
+
+        data.extend_from_slice(&current_index.to_le_bytes());
+        data
+    }
+
+    // Prepend the current version byte to a serialized CounterAccount, the way it is stored on-chain.
+    fn versioned(account: CounterAccount) -> Vec<u8> {
+        let mut data = vec![CURRENT_VERSION];
+        data.extend(account.try_to_vec().unwrap());
+        data
+    }
 
     // This function is a test function for the counter program.
     #[test]
@@ -91,10 +311,15 @@ mod test {
         let key = Pubkey::default();
         // Initialize the amount of lamports to 0.
         let mut lamports = 0;
-        // Initialize the data vector with 0s, its length is the size of u32.
-        let mut data = vec![0; mem::size_of::<u32>()];
-        // Initialize the owner with the default value.
-        let owner = Pubkey::default();
+        // The authority permitted to mutate the counter; the account owner is the program itself.
+        let authority_key = Pubkey::default();
+        // Initialize the data vector with a counter of 0 and the authority above.
+        let mut data = versioned(CounterAccount {
+            counter: 0,
+            authority: authority_key,
+        });
+        // Initialize the owner with the program ID, since process_instruction checks ownership.
+        let owner = program_id;
 
         // Create an AccountInfo object with the initialized values.
         let account = AccountInfo::new(
@@ -108,8 +333,22 @@ mod test {
             Epoch::default(), // Current epoch
         );
 
-        // Create a vector containing the account created above.
-        let accounts = vec![account];
+        // Create the signing authority account expected alongside the counter account.
+        let mut authority_lamports = 0;
+        let mut authority_data = vec![];
+        let authority_account = AccountInfo::new(
+            &authority_key,
+            true, // Is signer
+            false,
+            &mut authority_lamports,
+            &mut authority_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        // Create a vector containing the counter account and its authority.
+        let accounts = vec![account, authority_account];
 
         // Initialize instruction data vectors for different operations.
         let increment_instruction_data: Vec<u8> = vec![0];
@@ -121,7 +360,7 @@ mod test {
         process_instruction(&program_id, &accounts, &increment_instruction_data).unwrap();
         // Assert that the counter value in the account data has been incremented to 1.
         assert_eq!(
-            CounterAccount::try_from_slice(&accounts[0].data.borrow())
+            CounterAccount::try_from_slice(&accounts[0].data.borrow()[1..])
                 .unwrap()
                 .counter,
             1
@@ -131,7 +370,7 @@ mod test {
         process_instruction(&program_id, &accounts, &decrement_instruction_data).unwrap();
         // Assert that the counter value in the account data has been decremented to 0.
         assert_eq!(
-            CounterAccount::try_from_slice(&accounts[0].data.borrow())
+            CounterAccount::try_from_slice(&accounts[0].data.borrow()[1..])
                 .unwrap()
                 .counter,
             0
@@ -146,7 +385,7 @@ mod test {
         process_instruction(&program_id, &accounts, &update_instruction_data).unwrap();
         // Assert that the counter value in the account data has been updated to 33.
         assert_eq!(
-            CounterAccount::try_from_slice(&accounts[0].data.borrow())
+            CounterAccount::try_from_slice(&accounts[0].data.borrow()[1..])
                 .unwrap()
                 .counter,
             33
@@ -156,10 +395,562 @@ mod test {
         process_instruction(&program_id, &accounts, &reset_instruction_data).unwrap();
         // Assert that the counter value in the account data has been reset to 0.
         assert_eq!(
-            CounterAccount::try_from_slice(&accounts[0].data.borrow())
+            CounterAccount::try_from_slice(&accounts[0].data.borrow()[1..])
                 .unwrap()
                 .counter,
             0
         );
     }
+
+    // This function tests that incrementing past u32::MAX returns an error instead of wrapping.
+    #[test]
+    fn test_increment_overflow() {
+        // Initialize the program ID with the default value.
+        let program_id = Pubkey::default();
+        // Initialize the key with the default value.
+        let key = Pubkey::default();
+        // Initialize the amount of lamports to 0.
+        let mut lamports = 0;
+        // The authority permitted to mutate the counter.
+        let authority_key = Pubkey::default();
+        // Initialize the account data with a counter already at u32::MAX.
+        let mut data = versioned(CounterAccount {
+            counter: u32::MAX,
+            authority: authority_key,
+        });
+        // Initialize the owner with the program ID, since process_instruction checks ownership.
+        let owner = program_id;
+
+        // Create an AccountInfo object with the initialized values.
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        // Create the signing authority account expected alongside the counter account.
+        let mut authority_lamports = 0;
+        let mut authority_data = vec![];
+        let authority_account = AccountInfo::new(
+            &authority_key,
+            true,
+            false,
+            &mut authority_lamports,
+            &mut authority_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+        let accounts = vec![account, authority_account];
+
+        // Build increment instruction data that asks for an explicit increment of 1.
+        let mut increment_instruction_data: Vec<u8> = vec![0];
+        increment_instruction_data.extend_from_slice(&1u32.to_le_bytes());
+
+        // Incrementing u32::MAX by 1 must fail rather than wrap around to 0.
+        assert!(process_instruction(&program_id, &accounts, &increment_instruction_data).is_err());
+    }
+
+    // This function tests that decrementing below 0 returns an error instead of wrapping.
+    #[test]
+    fn test_decrement_underflow() {
+        // Initialize the program ID with the default value.
+        let program_id = Pubkey::default();
+        // Initialize the key with the default value.
+        let key = Pubkey::default();
+        // Initialize the amount of lamports to 0.
+        let mut lamports = 0;
+        // The authority permitted to mutate the counter.
+        let authority_key = Pubkey::default();
+        // Initialize the account data with a counter already at 0.
+        let mut data = versioned(CounterAccount {
+            counter: 0,
+            authority: authority_key,
+        });
+        // Initialize the owner with the program ID, since process_instruction checks ownership.
+        let owner = program_id;
+
+        // Create an AccountInfo object with the initialized values.
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        // Create the signing authority account expected alongside the counter account.
+        let mut authority_lamports = 0;
+        let mut authority_data = vec![];
+        let authority_account = AccountInfo::new(
+            &authority_key,
+            true,
+            false,
+            &mut authority_lamports,
+            &mut authority_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+        let accounts = vec![account, authority_account];
+
+        // Build decrement instruction data that asks for an explicit decrement of 1.
+        let mut decrement_instruction_data: Vec<u8> = vec![1];
+        decrement_instruction_data.extend_from_slice(&1u32.to_le_bytes());
+
+        // Decrementing 0 by 1 must fail rather than wrap around to u32::MAX.
+        assert!(process_instruction(&program_id, &accounts, &decrement_instruction_data).is_err());
+    }
+
+    // This function tests that a non-owner account is rejected before any mutation is attempted.
+    #[test]
+    fn test_rejects_wrong_owner() {
+        // Initialize the program ID with the default value.
+        let program_id = Pubkey::default();
+        // Use a distinct owner so it does not match the program ID.
+        let owner = Pubkey::new_from_array([1; 32]);
+        // Initialize the key with the default value.
+        let key = Pubkey::default();
+        // Initialize the amount of lamports to 0.
+        let mut lamports = 0;
+        // Initialize the account data with a counter of 0.
+        let mut data = versioned(CounterAccount {
+            counter: 0,
+            authority: Pubkey::default(),
+        });
+
+        // Create an AccountInfo object owned by a key other than the program ID.
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+        let accounts = vec![account];
+
+        let increment_instruction_data: Vec<u8> = vec![0];
+
+        // An account this program doesn't own must be rejected.
+        assert!(process_instruction(&program_id, &accounts, &increment_instruction_data).is_err());
+    }
+
+    // This function tests that mutating without the authority's signature is rejected.
+    #[test]
+    fn test_rejects_missing_authority_signature() {
+        // Initialize the program ID with the default value.
+        let program_id = Pubkey::default();
+        // Initialize the key with the default value.
+        let key = Pubkey::default();
+        // Initialize the amount of lamports to 0.
+        let mut lamports = 0;
+        // The authority permitted to mutate the counter.
+        let authority_key = Pubkey::default();
+        // Initialize the account data with a counter of 0.
+        let mut data = versioned(CounterAccount {
+            counter: 0,
+            authority: authority_key,
+        });
+        let owner = program_id;
+
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        // Build the authority account without the signer flag set.
+        let mut authority_lamports = 0;
+        let mut authority_data = vec![];
+        let authority_account = AccountInfo::new(
+            &authority_key,
+            false, // Not a signer
+            false,
+            &mut authority_lamports,
+            &mut authority_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+        let accounts = vec![account, authority_account];
+
+        let increment_instruction_data: Vec<u8> = vec![0];
+
+        // Without the authority's signature the instruction must be rejected.
+        assert!(process_instruction(&program_id, &accounts, &increment_instruction_data).is_err());
+    }
+
+    // This function tests that Initialize rejects a counter account that doesn't match the PDA
+    // derived from the payer, before any System Program CPI is attempted.
+    #[test]
+    fn test_initialize_rejects_pda_mismatch() {
+        let program_id = Pubkey::default();
+
+        // The fee-payer whose key the PDA should be derived from.
+        let payer_key = Pubkey::new_from_array([1; 32]);
+        let mut payer_lamports = 0;
+        let mut payer_data = vec![];
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &payer_key,
+            false,
+            Epoch::default(),
+        );
+
+        // An account key that is not the PDA derived from [COUNTER_SEED, payer_key].
+        let wrong_key = Pubkey::new_from_array([2; 32]);
+        let mut counter_lamports = 0;
+        let mut counter_data = vec![];
+        let counter_account = AccountInfo::new(
+            &wrong_key,
+            false,
+            true,
+            &mut counter_lamports,
+            &mut counter_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        // Never inspected before the PDA check returns, so any key will do.
+        let system_program_key = Pubkey::default();
+        let mut system_program_lamports = 0;
+        let mut system_program_data = vec![];
+        let system_program = AccountInfo::new(
+            &system_program_key,
+            false,
+            false,
+            &mut system_program_lamports,
+            &mut system_program_data,
+            &system_program_key,
+            false,
+            Epoch::default(),
+        );
+
+        let accounts = vec![payer, counter_account, system_program];
+
+        let initialize_instruction_data: Vec<u8> = vec![4];
+
+        // An account that doesn't match the PDA derived from the payer must be rejected.
+        assert!(process_instruction(&program_id, &accounts, &initialize_instruction_data).is_err());
+    }
+
+    // This function tests that UpdateGuarded succeeds when no sibling instruction targets the
+    // caller-supplied forbidden program.
+    #[test]
+    fn test_update_guarded_allows_unrelated_transaction() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let mut lamports = 0;
+        let authority_key = Pubkey::default();
+        let mut data = versioned(CounterAccount {
+            counter: 0,
+            authority: authority_key,
+        });
+        let owner = program_id;
+
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        let mut authority_lamports = 0;
+        let mut authority_data = vec![];
+        let authority_account = AccountInfo::new(
+            &authority_key,
+            true,
+            false,
+            &mut authority_lamports,
+            &mut authority_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        // The only sibling instruction in this transaction targets an unrelated program.
+        let forbidden_program = Pubkey::new_from_array([9; 32]);
+        let unrelated_program = Pubkey::new_from_array([1; 32]);
+        let instructions_sysvar_key = instructions_sysvar::id();
+        let mut instructions_sysvar_lamports = 0;
+        let mut instructions_sysvar_data = build_instructions_sysvar_data(&[unrelated_program], 0);
+        let instructions_sysvar_account = AccountInfo::new(
+            &instructions_sysvar_key,
+            false,
+            false,
+            &mut instructions_sysvar_lamports,
+            &mut instructions_sysvar_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        let accounts = vec![account, authority_account, instructions_sysvar_account];
+
+        let mut update_guarded_instruction_data: Vec<u8> = vec![5];
+        update_guarded_instruction_data.extend_from_slice(&42u32.to_le_bytes());
+        update_guarded_instruction_data.extend_from_slice(&forbidden_program.to_bytes());
+
+        process_instruction(&program_id, &accounts, &update_guarded_instruction_data).unwrap();
+
+        // The counter should have been updated since the forbidden program never appears.
+        assert_eq!(
+            CounterAccount::try_from_slice(&accounts[0].data.borrow()[1..])
+                .unwrap()
+                .counter,
+            42
+        );
+    }
+
+    // This function tests that UpdateGuarded is rejected when a sibling instruction - even one
+    // that comes after UpdateGuarded in the transaction - targets the forbidden program.
+    #[test]
+    fn test_update_guarded_rejects_forbidden_sibling() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let mut lamports = 0;
+        let authority_key = Pubkey::default();
+        let mut data = versioned(CounterAccount {
+            counter: 0,
+            authority: authority_key,
+        });
+        let owner = program_id;
+
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        let mut authority_lamports = 0;
+        let mut authority_data = vec![];
+        let authority_account = AccountInfo::new(
+            &authority_key,
+            true,
+            false,
+            &mut authority_lamports,
+            &mut authority_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        // UpdateGuarded is instruction 0; the forbidden program is bundled in afterward at index 1.
+        let forbidden_program = Pubkey::new_from_array([9; 32]);
+        let instructions_sysvar_key = instructions_sysvar::id();
+        let mut instructions_sysvar_lamports = 0;
+        let mut instructions_sysvar_data =
+            build_instructions_sysvar_data(&[program_id, forbidden_program], 0);
+        let instructions_sysvar_account = AccountInfo::new(
+            &instructions_sysvar_key,
+            false,
+            false,
+            &mut instructions_sysvar_lamports,
+            &mut instructions_sysvar_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        let accounts = vec![account, authority_account, instructions_sysvar_account];
+
+        let mut update_guarded_instruction_data: Vec<u8> = vec![5];
+        update_guarded_instruction_data.extend_from_slice(&42u32.to_le_bytes());
+        update_guarded_instruction_data.extend_from_slice(&forbidden_program.to_bytes());
+
+        // A forbidden program bundled after UpdateGuarded in the same transaction must be caught.
+        assert!(
+            process_instruction(&program_id, &accounts, &update_guarded_instruction_data).is_err()
+        );
+    }
+
+    // This function tests that the first signer to submit `ClaimAuthority` against an untouched
+    // legacy V1 account is accepted as its authority, since a true pre-`Initialize` V1 account's
+    // key has no relationship to any PDA and so has no "original creator" that could be verified
+    // instead. `process_instruction` confines this claim to `ClaimAuthority` specifically - see
+    // `test_non_claim_instruction_rejected_on_unclaimed_legacy_account` below.
+    //
+    // NOTE: this only exercises `migrate`, not the dispatch logic or a full process_instruction
+    // call - doing that here would require the Rent sysvar, which this harness can't provide, and
+    // this harness's hand-built AccountInfo (a plain Vec with no BPF entrypoint header in front of
+    // it) to survive the account.realloc() call later in process_instruction, which isn't safe
+    // outside a real entrypoint. See the CPI note on initialize_counter for the same kind of gap; a real
+    // end-to-end test of this path belongs in a solana-program-test harness once this crate has a
+    // manifest to add that dev-dependency to.
+    #[test]
+    fn test_first_signer_claims_authority_over_legacy_account() {
+        // A legacy, unversioned V1 account: just a bare counter, no authority, no version byte.
+        let legacy_data = crate::state::CounterAccountV1 { counter: 7 }.try_to_vec().unwrap();
+
+        // This signer's key has no relationship to the account's key at all, yet migrate() still
+        // stamps it in as the authority since nobody else has claimed the account yet.
+        let authority_key = Pubkey::new_from_array([5; 32]);
+        let (migrated, did_migrate) = migrate(&legacy_data, authority_key).unwrap();
+
+        assert!(did_migrate);
+        assert_eq!(migrated.counter, 7);
+        assert_eq!(migrated.authority, authority_key);
+    }
+
+    // This function tests that an instruction other than `ClaimAuthority` is rejected outright
+    // against an unclaimed legacy account, instead of silently claiming authority as a side
+    // effect the way any mutating instruction used to. The rejection happens before `realloc` is
+    // ever reached, so this is safe to exercise through the full `process_instruction` entry
+    // point in this harness.
+    #[test]
+    fn test_non_claim_instruction_rejected_on_unclaimed_legacy_account() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let mut lamports = 0;
+        let mut data = crate::state::CounterAccountV1 { counter: 7 }.try_to_vec().unwrap();
+        let owner = program_id;
+
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        // An opportunistic signer with no relationship to this account at all.
+        let attacker_key = Pubkey::new_from_array([5; 32]);
+        let mut attacker_lamports = 0;
+        let mut attacker_data = vec![];
+        let attacker_account = AccountInfo::new(
+            &attacker_key,
+            true,
+            false,
+            &mut attacker_lamports,
+            &mut attacker_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        let accounts = vec![account, attacker_account];
+        let increment_instruction_data: Vec<u8> = vec![0];
+
+        // Incrementing an unclaimed legacy account must be rejected, not silently treated as a
+        // claim of authority by whoever happened to submit it first.
+        assert!(
+            process_instruction(&program_id, &accounts, &increment_instruction_data).is_err()
+        );
+    }
+
+    // This function tests that `ClaimAuthority` is rejected against an account that has already
+    // been claimed (i.e. is already version-tagged), even when submitted by its real authority.
+    #[test]
+    fn test_claim_authority_rejected_on_already_claimed_account() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let mut lamports = 0;
+        let authority_key = Pubkey::default();
+        let mut data = versioned(CounterAccount {
+            counter: 0,
+            authority: authority_key,
+        });
+        let owner = program_id;
+
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+
+        let mut authority_lamports = 0;
+        let mut authority_data = vec![];
+        let authority_account = AccountInfo::new(
+            &authority_key,
+            true,
+            false,
+            &mut authority_lamports,
+            &mut authority_data,
+            &owner,
+            false,
+            Epoch::default(),
+        );
+        let accounts = vec![account, authority_account];
+
+        let claim_authority_instruction_data: Vec<u8> = vec![6];
+
+        assert!(process_instruction(
+            &program_id,
+            &accounts,
+            &claim_authority_instruction_data
+        )
+        .is_err());
+    }
+
+    // This function tests that the bytes Initialize writes after its CPI decode back into a
+    // zeroed counter whose authority is the payer. The CPI itself isn't exercised here - see the note on
+    // initialize_counter.
+    #[test]
+    fn test_initial_counter_account_data_matches_expected_layout() {
+        let payer_key = Pubkey::new_from_array([8; 32]);
+        let data = initial_counter_account_data(&payer_key);
+
+        assert_eq!(data.len(), COUNTER_ACCOUNT_SPACE);
+        assert_eq!(data[0], CURRENT_VERSION);
+
+        let decoded = CounterAccount::try_from_slice(&data[1..]).unwrap();
+        assert_eq!(decoded.counter, 0);
+        assert_eq!(decoded.authority, payer_key);
+    }
+
+    // This function tests that a legacy account funded below the rent-exempt minimum for its
+    // post-migration size is rejected rather than handed to `realloc`.
+    #[test]
+    fn test_ensure_rent_exempt_rejects_underfunded_legacy_account() {
+        let required_lamports = 1_000_000;
+        let underfunded_lamports = required_lamports - 1;
+
+        assert!(ensure_rent_exempt(underfunded_lamports, required_lamports).is_err());
+    }
+
+    // This function tests that an account already holding the rent-exempt minimum is accepted.
+    #[test]
+    fn test_ensure_rent_exempt_allows_sufficiently_funded_account() {
+        let required_lamports = 1_000_000;
+
+        assert!(ensure_rent_exempt(required_lamports, required_lamports).is_ok());
+    }
 }
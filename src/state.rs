@@ -0,0 +1,132 @@
+// Account-state definitions and schema migration for the counter program
+use borsh::{BorshDeserialize, BorshSerialize}; // Import traits for serialization and deserialization
+use borsh_derive::{BorshDeserialize, BorshSerialize}; // Import macros for deriving serialization and deserialization
+use solana_program::{program_error::ProgramError, pubkey::Pubkey}; // Import the ProgramError type and Pubkey for account references
+use std::mem; // Import the mem module from the standard library for memory manipulation
+
+// The schema version written as the leading byte of every account created by this program version
+pub const CURRENT_VERSION: u8 = 2;
+
+// The original, pre-versioning account layout: a bare counter with no authority or version byte
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+pub struct CounterAccountV1 {
+    pub counter: u32, // Define a public field named "counter" of type u32
+}
+
+// The current account layout: the counter plus the authority permitted to mutate it. Stored
+// behind a leading `CURRENT_VERSION` byte so future layouts can keep migrating forward.
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+pub struct CounterAccountV2 {
+    pub counter: u32,      // Define a public field named "counter" of type u32
+    pub authority: Pubkey, // The only signer permitted to mutate this counter
+}
+
+// `CounterAccount` always refers to the latest schema
+pub type CounterAccount = CounterAccountV2;
+
+// The size of the untagged V2 layout written directly by chunk0-3's `Initialize`, before version
+// bytes existed: a bare `counter` followed by `authority`, with no leading discriminator.
+const UNTAGGED_V2_SPACE: usize = mem::size_of::<u32>() + mem::size_of::<Pubkey>();
+
+// Deserialize `data`, transparently upgrading either on-disk shape that predates `CURRENT_VERSION`
+// to the latest, version-tagged layout:
+//   - the original, pre-chunk0-3 V1 layout: a bare `u32` counter, no authority, no version byte.
+//     `default_authority` is stamped in as the authority of any account upgraded from this shape,
+//     since V1 accounts have no authority of their own.
+//   - the untagged V2 layout chunk0-3 wrote directly (`counter` + `authority`, no version byte).
+//     Its authority is already genuine, not defaulted, so it just needs the version byte added.
+// The returned `bool` is `true` exactly when an authority was defaulted (the V1 case), so callers
+// can gate who is allowed to claim that stamped-in authority.
+pub fn migrate(
+    data: &[u8],
+    default_authority: Pubkey,
+) -> Result<(CounterAccountV2, bool), ProgramError> {
+    // V1 accounts were sized for a bare u32 with no version byte at all; anything wider already
+    // carries either the untagged or version-prefixed V2 layout.
+    if data.len() <= mem::size_of::<u32>() {
+        let legacy = CounterAccountV1::try_from_slice(data)?;
+        return Ok((
+            CounterAccountV2 {
+                counter: legacy.counter,
+                authority: default_authority,
+            },
+            true,
+        ));
+    }
+
+    // Accounts created while chunk0-3 was live are exactly this size and carry no version byte;
+    // everything wider is the version-prefixed layout this function also writes going forward.
+    if data.len() == UNTAGGED_V2_SPACE {
+        return Ok((CounterAccountV2::try_from_slice(data)?, false));
+    }
+
+    let (&version, payload) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    match version {
+        CURRENT_VERSION => Ok((CounterAccountV2::try_from_slice(payload)?, false)),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+// This module contains tests for account schema migration.
+#[cfg(test)]
+mod test {
+    // Import all items from the parent module into the current scope.
+    use super::*;
+
+    // This function tests that legacy, unversioned V1 data upgrades in place to V2.
+    #[test]
+    fn test_migrate_legacy_v1_account() {
+        // Encode a V1 account the way it looked before versioning existed.
+        let legacy = CounterAccountV1 { counter: 42 }.try_to_vec().unwrap();
+        let default_authority = Pubkey::new_from_array([7; 32]);
+
+        // Migrating should upgrade the bare counter and stamp in the default authority.
+        let (migrated, did_migrate) = migrate(&legacy, default_authority).unwrap();
+
+        assert_eq!(migrated.counter, 42);
+        assert_eq!(migrated.authority, default_authority);
+        assert!(did_migrate);
+    }
+
+    // This function tests that an already-versioned V2 account passes through unchanged.
+    #[test]
+    fn test_migrate_current_v2_account_is_noop() {
+        // Encode a V2 account behind the current version byte.
+        let account = CounterAccountV2 {
+            counter: 9,
+            authority: Pubkey::new_from_array([3; 32]),
+        };
+        let mut data = vec![CURRENT_VERSION];
+        data.extend(account.try_to_vec().unwrap());
+
+        // Migrating an already-current account must preserve its stored authority, not the default.
+        let (migrated, did_migrate) = migrate(&data, Pubkey::default()).unwrap();
+
+        assert_eq!(migrated.counter, 9);
+        assert_eq!(migrated.authority, account.authority);
+        assert!(!did_migrate);
+    }
+
+    // This function tests that the untagged V2 layout written by chunk0-3 (counter + authority,
+    // no version byte) migrates to the version-tagged layout without losing its real authority.
+    #[test]
+    fn test_migrate_untagged_v2_account() {
+        // Encode an account the way chunk0-3's Initialize wrote it: no leading version byte.
+        let account = CounterAccountV2 {
+            counter: 5,
+            authority: Pubkey::new_from_array([6; 32]),
+        };
+        let data = account.try_to_vec().unwrap();
+        assert_eq!(data.len(), UNTAGGED_V2_SPACE);
+
+        // Migrating must preserve the real, already-genuine authority, not the default.
+        let (migrated, did_migrate) = migrate(&data, Pubkey::default()).unwrap();
+
+        assert_eq!(migrated.counter, 5);
+        assert_eq!(migrated.authority, account.authority);
+        assert!(!did_migrate);
+    }
+}